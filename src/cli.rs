@@ -32,7 +32,13 @@ pub enum Action {
     Help(String, PathBuf),
     /// A string listing all found executables on `PATH`.
     ///
+    /// This includes any interpreter [`crate::all_executables`] discovers by
+    /// name on `PATH` -- not just `python`/`python3`, but e.g. a
+    /// `python3.12` found further down `PATH` than the generic names.
+    ///
     /// The string is formatted to be human-readable.
+    /// When JSON output is requested (see [`Action::from_main`]), it is a
+    /// JSON array instead.
     List(String),
     /// Details for executing a Python executable.
     Execute {
@@ -69,6 +75,10 @@ impl Action {
     ///
     /// The list of executable is gathered via [`crate::all_executables`].
     ///
+    /// A trailing `--json` argument (e.g. `--list --json`), or the
+    /// `PY_LIST_FORMAT` environment variable set to `json`, renders the
+    /// list as a JSON array instead of the default human-readable table.
+    ///
     /// ## Version Restriction
     ///
     /// Returns the appropriate [`Action::Execute`] instance for the requested
@@ -76,6 +86,17 @@ impl Action {
     ///
     /// [`crate::find_executable`] is used to perform the search.
     ///
+    /// ## `--any`/`-a`
+    ///
+    /// Returns an [`Action::Execute`] instance, searching with an explicit
+    /// [`RequestedVersion::Any`] instead of the implicit
+    /// [`RequestedVersion::Default`] used when no arguments are given. This
+    /// means pre-release builds and alternate implementations (e.g. PyPy)
+    /// are eligible to be chosen, which they otherwise are not.
+    ///
+    /// Combining `--any`/`-a` with a version flag is rejected with
+    /// [`crate::Error::IllegalArgument`], since the two are contradictory.
+    ///
     /// ## No Arguments for the Launcher
     ///
     /// Returns an [`Action::Execute`] instance.
@@ -85,9 +106,18 @@ impl Action {
     /// virtual environment in a directory named by [`DEFAULT_VENV_DIR`] in the
     /// current or any parent directories.
     ///
-    /// If no virtual environment is found, a shebang line is searched for in
-    /// the first argument to the Python interpreter. If one is found then it
-    /// is used to (potentially) restrict the requested version searched for.
+    /// If no virtual environment is found, the current and parent directories
+    /// are searched for a `.python-version` file. If one is found then its
+    /// contents are used to (potentially) restrict the requested version
+    /// searched for.
+    ///
+    /// If neither a virtual environment nor a `.python-version` file is
+    /// found, a shebang line is searched for in the first argument to the
+    /// Python interpreter. If one is found then it is used to (potentially)
+    /// restrict the requested version searched for.
+    ///
+    /// Otherwise the search proceeds with [`RequestedVersion::Default`],
+    /// which -- unlike `--any`/`-a` -- only considers stable CPython builds.
     ///
     /// The search for an interpreter proceeds using [`crate::find_executable`].
     ///
@@ -95,6 +125,11 @@ impl Action {
     ///
     /// If `-h`, `--help`, or `--list` are specified as the first argument but
     /// there are other arguments, [`crate::Error::IllegalArgument`] is returned.
+    /// A single `--json` directly after `--list` does not count as another
+    /// argument.
+    ///
+    /// If `--any`/`-a` is combined with a version flag,
+    /// [`crate::Error::IllegalArgument`] is returned.
     ///
     /// If no executable could be found for [`Action::Help`] or
     /// [`Action::List`], [`crate::Error::NoExecutableFound`] is returned.
@@ -107,14 +142,26 @@ impl Action {
         let launcher_path = PathBuf::from(&argv[0]); // Strip the path to this executable.
 
         match argv.get(1) {
-            Some(flag) if flag == "-h" || flag == "--help" || flag == "--list" => {
+            Some(flag) if flag == "--list" => {
+                let json_flag = argv.get(2).is_some_and(|arg| arg == "--json");
+                if argv.len() > 2 + usize::from(json_flag) {
+                    Err(crate::Error::IllegalArgument(
+                        launcher_path,
+                        flag.to_string(),
+                    ))
+                } else {
+                    Ok(Action::List(list_executables(
+                        &crate::all_executables(),
+                        ListFormat::from_json_flag(json_flag),
+                    )?))
+                }
+            }
+            Some(flag) if flag == "-h" || flag == "--help" => {
                 if argv.len() > 2 {
                     Err(crate::Error::IllegalArgument(
                         launcher_path,
                         flag.to_string(),
                     ))
-                } else if flag == "--list" {
-                    Ok(Action::List(list_executables(&crate::all_executables())?))
                 } else {
                     crate::find_executable(RequestedVersion::Any)
                         .ok_or(crate::Error::NoExecutableFound(RequestedVersion::Any))
@@ -126,6 +173,21 @@ impl Action {
                         })
                 }
             }
+            Some(flag) if flag == "--any" || flag == "-a" => {
+                if argv.get(2).and_then(|arg| version_from_flag(arg)).is_some() {
+                    Err(crate::Error::IllegalArgument(
+                        launcher_path,
+                        flag.to_string(),
+                    ))
+                } else {
+                    Ok(Action::Execute {
+                        launcher_path,
+                        // Make sure to skip the app path and the `--any`/`-a` flag.
+                        executable: find_executable(RequestedVersion::Any, &argv[2..])?,
+                        args: argv[2..].to_vec(),
+                    })
+                }
+            }
             Some(version) if version_from_flag(version).is_some() => {
                 Ok(Action::Execute {
                     launcher_path,
@@ -137,7 +199,7 @@ impl Action {
             Some(_) | None => Ok(Action::Execute {
                 launcher_path,
                 // Make sure to skip the app path.
-                executable: find_executable(RequestedVersion::Any, &argv[1..])?,
+                executable: find_executable(RequestedVersion::Default, &argv[1..])?,
                 args: argv[1..].to_vec(),
             }),
         }
@@ -161,15 +223,119 @@ fn help_message(launcher_path: &Path, executable_path: &Path) -> String {
 ///
 /// It is assumed that the flag from the command-line is passed as-is
 /// (i.e. the flag starts with `-`).
+///
+/// As well as a bare major or major.minor version (e.g. `-3`, `-3.6`), a
+/// bounded specifier such as `-=3.11` (i.e. "at least 3.11") or a
+/// comma-delimited range such as `->=3.11,<3.13` is accepted and parsed into
+/// a [`RequestedVersion::Range`].
 fn version_from_flag(arg: &str) -> Option<RequestedVersion> {
     if !arg.starts_with('-') {
         None
     } else {
-        RequestedVersion::from_str(&arg[1..]).ok()
+        let spec = &arg[1..];
+        range_from_spec(spec).or_else(|| RequestedVersion::from_str(spec).ok())
     }
 }
 
-fn list_executables(executables: &HashMap<ExactVersion, PathBuf>) -> crate::Result<String> {
+/// Parses a bounded version specifier (e.g. `=3.11` or `>=3.11,<3.13`) into a
+/// [`RequestedVersion::Range`].
+///
+/// Each comma-separated clause is one of `=`, `>=`, `>`, `<=`, or `<`
+/// followed by a `major.minor` version. A bare `=`/`>=` clause is treated as
+/// an inclusive lower bound and a bare `<=`/`<` clause as an upper bound, so
+/// `=3.11` means "3.11 or newer".
+fn range_from_spec(spec: &str) -> Option<RequestedVersion> {
+    if !matches!(spec.chars().next(), Some('=' | '>' | '<')) {
+        return None;
+    }
+
+    let mut lower = None;
+    let mut lower_inclusive = false;
+    let mut upper = None;
+    let mut upper_inclusive = false;
+
+    for clause in spec.split(',') {
+        let (op, version) = if let Some(version) = clause.strip_prefix(">=") {
+            (">=", version)
+        } else if let Some(version) = clause.strip_prefix("<=") {
+            ("<=", version)
+        } else if let Some(version) = clause.strip_prefix('=') {
+            ("=", version)
+        } else if let Some(version) = clause.strip_prefix('>') {
+            (">", version)
+        } else if let Some(version) = clause.strip_prefix('<') {
+            ("<", version)
+        } else {
+            return None;
+        };
+
+        let mut parts = version.splitn(2, '.');
+        let major: u8 = parts.next()?.parse().ok()?;
+        let minor: u8 = parts.next()?.parse().ok()?;
+
+        match op {
+            "=" | ">=" => {
+                lower = Some((major, minor));
+                lower_inclusive = true;
+            }
+            ">" => {
+                lower = Some((major, minor));
+                lower_inclusive = false;
+            }
+            "<=" => {
+                upper = Some((major, minor));
+                upper_inclusive = true;
+            }
+            "<" => {
+                upper = Some((major, minor));
+                upper_inclusive = false;
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    if lower.is_none() && upper.is_none() {
+        return None;
+    }
+
+    Some(RequestedVersion::Range {
+        lower,
+        upper,
+        lower_inclusive,
+        upper_inclusive,
+    })
+}
+
+/// The rendering used for [`Action::List`]'s output.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ListFormat {
+    /// The default, human-readable box-drawing table.
+    Table,
+    /// A stable, newest-first JSON array of `{"version": ..., "path": ...}`
+    /// objects for tooling to consume.
+    Json,
+}
+
+impl ListFormat {
+    /// Determines the format to use, honouring an explicit `--json` flag
+    /// over the `PY_LIST_FORMAT` environment variable, defaulting to
+    /// [`ListFormat::Table`].
+    fn from_json_flag(json_flag: bool) -> Self {
+        if json_flag {
+            return ListFormat::Json;
+        }
+
+        match env::var("PY_LIST_FORMAT") {
+            Ok(value) if value.eq_ignore_ascii_case("json") => ListFormat::Json,
+            _ => ListFormat::Table,
+        }
+    }
+}
+
+fn list_executables(
+    executables: &HashMap<ExactVersion, PathBuf>,
+    format: ListFormat,
+) -> crate::Result<String> {
     if executables.is_empty() {
         return Err(crate::Error::NoExecutableFound(RequestedVersion::Any));
     }
@@ -178,6 +344,13 @@ fn list_executables(executables: &HashMap<ExactVersion, PathBuf>) -> crate::Resu
     executable_pairs.sort_unstable();
     executable_pairs.reverse();
 
+    Ok(match format {
+        ListFormat::Table => list_executables_table(&executable_pairs),
+        ListFormat::Json => list_executables_json(&executable_pairs),
+    })
+}
+
+fn list_executables_table(executable_pairs: &[(&ExactVersion, &PathBuf)]) -> String {
     let mut table = Table::new();
     table.load_preset(comfy_table::presets::NOTHING);
     // Using U+2502/"Box Drawings Light Vertical" over
@@ -190,7 +363,53 @@ fn list_executables(executables: &HashMap<ExactVersion, PathBuf>) -> crate::Resu
         table.add_row(vec![version.to_string(), path.display().to_string()]);
     }
 
-    Ok(table.to_string() + "\n")
+    table.to_string() + "\n"
+}
+
+fn list_executables_json(executable_pairs: &[(&ExactVersion, &PathBuf)]) -> String {
+    let mut json = String::from("[");
+
+    for (index, (version, path)) in executable_pairs.iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+        write!(
+            json,
+            r#"{{"version":"{}","path":"{}"}}"#,
+            version,
+            json_escape_str(&path.display().to_string())
+        )
+        .unwrap();
+    }
+
+    json.push(']');
+    json.push('\n');
+    json
+}
+
+/// Escapes `value` for embedding in a JSON string literal.
+///
+/// Handles the characters that are illegal unescaped inside a JSON string:
+/// `\`, `"`, and control characters (`\u{0}`..=`\u{1f}`), via the standard
+/// one-letter shorthands where one exists and a `\u00XX` escape otherwise.
+fn json_escape_str(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{8}' => escaped.push_str("\\b"),
+            '\u{c}' => escaped.push_str("\\f"),
+            c if (c as u32) < 0x20 => {
+                write!(escaped, "\\u{:04x}", c as u32).unwrap();
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
 }
 
 fn relative_venv_path(add_default: bool) -> PathBuf {
@@ -232,8 +451,7 @@ fn venv_path_search() -> Option<PathBuf> {
         cwd.ancestors().find_map(|path| {
             let venv_path = path.join(relative_venv_path(true));
             log::info!("Checking {}", venv_path.display());
-            // bool::then_some() makes more sense, but still experimental.
-            venv_path.is_file().then(|| venv_path)
+            venv_path.is_file().then_some(venv_path)
         })
     }
 }
@@ -242,6 +460,56 @@ fn venv_executable() -> Option<PathBuf> {
     activated_venv().or_else(venv_path_search)
 }
 
+/// Searches `env::current_dir()` and its ancestors for a `.python-version`
+/// file and returns the version it specifies, if any.
+///
+/// The first file found wins, walking from the current directory upward.
+/// Blank lines and lines starting with `#` are skipped when looking for the
+/// version to parse. A file whose first meaningful line fails to parse as a
+/// [`RequestedVersion`] is skipped in favour of continuing the search in
+/// parent directories.
+fn python_version_file_search() -> Option<RequestedVersion> {
+    if env::current_dir().is_err() {
+        log::warn!("current working directory is invalid");
+        return None;
+    }
+    python_version_from_ancestors(&env::current_dir().unwrap())
+}
+
+/// The ancestor walk underlying [`python_version_file_search`], taking the
+/// starting directory explicitly so it can be tested without touching the
+/// process' actual current directory.
+fn python_version_from_ancestors(start: &Path) -> Option<RequestedVersion> {
+    log::info!(
+        "Searching for a .python-version file in {} and parent directories",
+        start.display()
+    );
+    start.ancestors().find_map(|path| {
+        let version_file = path.join(".python-version");
+        log::info!("Checking {}", version_file.display());
+        if !version_file.is_file() {
+            return None;
+        }
+
+        let contents = std::fs::read_to_string(&version_file).ok()?;
+        let line = contents
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))?;
+
+        match RequestedVersion::from_str(line) {
+            Ok(version) => {
+                log::debug!("Found {:?} in {}", version, version_file.display());
+                Some(version)
+            }
+            Err(_) => {
+                log::warn!("Failed to parse version from {}", version_file.display());
+                None
+            }
+        }
+    })
+}
+
 // https://en.m.wikipedia.org/wiki/Shebang_(Unix)
 fn parse_python_shebang(reader: &mut impl Read) -> Option<RequestedVersion> {
     let mut shebang_buffer = [0; 2];
@@ -260,37 +528,55 @@ fn parse_python_shebang(reader: &mut impl Read) -> Option<RequestedVersion> {
         return None;
     };
 
-    // Whitespace between `#!` and the path is allowed.
-    let line = first_line.trim();
+    // Whitespace between `#!` and the path (and any subsequent arguments) is
+    // allowed.
+    let mut tokens = first_line.split_whitespace();
 
-    let accepted_paths = [
-        "python",
-        "/usr/bin/python",
-        "/usr/local/bin/python",
-        "/usr/bin/env python",
-    ];
+    let mut interpreter = tokens.next()?;
 
-    for acceptable_path in &accepted_paths {
-        if !line.starts_with(acceptable_path) {
-            continue;
-        }
+    // `#!/usr/bin/env python3.11` and `#!/usr/bin/env -S python3.11 -X foo`
+    // both name the actual interpreter to look up after `env`.
+    if Path::new(interpreter).file_name()?.to_str()? == "env" {
+        interpreter = match tokens.next()? {
+            "-S" => tokens.next()?,
+            other => other,
+        };
+    }
 
-        log::debug!("Found shebang: {}", acceptable_path);
-        let version = line[acceptable_path.len()..].to_string();
-        log::debug!("Found version: {}", version);
-        return RequestedVersion::from_str(&version).ok();
+    let basename = Path::new(interpreter).file_name()?.to_str()?;
+    log::debug!("Found shebang interpreter: {}", basename);
+    python_version_from_basename(basename)
+}
+
+/// Parses a shebang interpreter's basename (e.g. `python3.11` or `pypy3`)
+/// into the version it requests, if it names a Python implementation.
+///
+/// Anything that isn't `python`/`pypy` optionally followed by a version,
+/// such as `/bin/sh` or `python-config`, is rejected by returning `None`.
+fn python_version_from_basename(basename: &str) -> Option<RequestedVersion> {
+    let version = basename
+        .strip_prefix("python")
+        .or_else(|| basename.strip_prefix("pypy"))?;
+
+    if !version.is_empty() && !version.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
     }
 
-    None
+    RequestedVersion::from_str(version).ok()
 }
 
 fn find_executable(version: RequestedVersion, args: &[String]) -> crate::Result<PathBuf> {
     let mut requested_version = version;
     let mut chosen_path: Option<PathBuf> = None;
 
-    if requested_version == RequestedVersion::Any {
+    if matches!(
+        requested_version,
+        RequestedVersion::Any | RequestedVersion::Default
+    ) {
         if let Some(venv_path) = venv_executable() {
             chosen_path = Some(venv_path);
+        } else if let Some(version_file_version) = python_version_file_search() {
+            requested_version = version_file_version;
         } else if !args.is_empty() {
             // Using the first argument because it's the simplest and sanest.
             // We can't use the last argument because that could actually be an argument
@@ -333,12 +619,23 @@ fn find_executable(version: RequestedVersion, args: &[String]) -> crate::Result<
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Mutex;
+
+    use once_cell::sync::Lazy;
     use test_case::test_case;
 
     use super::*;
 
+    /// Guards tests that mutate the process-wide `PY_LIST_FORMAT` env var,
+    /// since `cargo test` runs tests in parallel by default and an
+    /// unsynchronized `env::set_var`/`env::remove_var` from one test could
+    /// otherwise be observed by another.
+    static PY_LIST_FORMAT_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
     #[test_case(&["py".to_string(), "--help".to_string(), "--list".to_string()] => Err(crate::Error::IllegalArgument(PathBuf::from("py"), "--help".to_string())))]
     #[test_case(&["py".to_string(), "--list".to_string(), "--help".to_string()] => Err(crate::Error::IllegalArgument(PathBuf::from("py"), "--list".to_string())))]
+    #[test_case(&["py".to_string(), "--any".to_string(), "-3.6".to_string()] => Err(crate::Error::IllegalArgument(PathBuf::from("py"), "--any".to_string())))]
+    #[test_case(&["py".to_string(), "-a".to_string(), "-3".to_string()] => Err(crate::Error::IllegalArgument(PathBuf::from("py"), "-a".to_string())))]
     fn from_main_illegal_argument_tests(argv: &[String]) -> crate::Result<Action> {
         Action::from_main(argv)
     }
@@ -349,10 +646,52 @@ mod tests {
     #[test_case("-3.6" => Some(RequestedVersion::Exact(3, 6)) ; "Exact/major.minor")]
     #[test_case("-42.13" => Some(RequestedVersion::Exact(42, 13)) ; "double-digit major & minor versions")]
     #[test_case("-3.6.4" => None ; "version flag with micro version is None")]
+    #[test_case("-=3.11" => Some(RequestedVersion::Range {
+        lower: Some((3, 11)),
+        upper: None,
+        lower_inclusive: true,
+        upper_inclusive: false,
+    }) ; "inclusive lower bound")]
+    #[test_case("->=3.11,<3.13" => Some(RequestedVersion::Range {
+        lower: Some((3, 11)),
+        upper: Some((3, 13)),
+        lower_inclusive: true,
+        upper_inclusive: false,
+    }) ; "bounded range")]
     fn version_from_flag_tests(flag: &str) -> Option<RequestedVersion> {
         version_from_flag(flag)
     }
 
+    #[test_case("" => None ; "empty spec is None")]
+    #[test_case("3.11" => None ; "missing operator is None")]
+    #[test_case("=3.11" => Some(RequestedVersion::Range {
+        lower: Some((3, 11)),
+        upper: None,
+        lower_inclusive: true,
+        upper_inclusive: false,
+    }) ; "equals is an inclusive lower bound")]
+    #[test_case(">3.11" => Some(RequestedVersion::Range {
+        lower: Some((3, 11)),
+        upper: None,
+        lower_inclusive: false,
+        upper_inclusive: false,
+    }) ; "exclusive lower bound")]
+    #[test_case("<=3.13" => Some(RequestedVersion::Range {
+        lower: None,
+        upper: Some((3, 13)),
+        lower_inclusive: false,
+        upper_inclusive: true,
+    }) ; "inclusive upper bound")]
+    #[test_case(">=3.11,<3.13" => Some(RequestedVersion::Range {
+        lower: Some((3, 11)),
+        upper: Some((3, 13)),
+        lower_inclusive: true,
+        upper_inclusive: false,
+    }) ; "lower and upper bound")]
+    fn range_from_spec_tests(spec: &str) -> Option<RequestedVersion> {
+        range_from_spec(spec)
+    }
+
     #[test]
     fn test_help_message() {
         let launcher_path = "/some/path/to/launcher";
@@ -369,30 +708,46 @@ mod tests {
         let mut executables: HashMap<ExactVersion, PathBuf> = HashMap::new();
 
         assert_eq!(
-            list_executables(&executables),
+            list_executables(&executables, ListFormat::Table),
+            Err(crate::Error::NoExecutableFound(RequestedVersion::Any))
+        );
+        assert_eq!(
+            list_executables(&executables, ListFormat::Json),
             Err(crate::Error::NoExecutableFound(RequestedVersion::Any))
         );
 
         let python27_path = "/path/to/2/7/python";
         executables.insert(
-            ExactVersion { major: 2, minor: 7 },
+            ExactVersion {
+                major: 2,
+                minor: 7,
+                ..ExactVersion::default()
+            },
             PathBuf::from(python27_path),
         );
         let python36_path = "/path/to/3/6/python";
         executables.insert(
-            ExactVersion { major: 3, minor: 6 },
+            ExactVersion {
+                major: 3,
+                minor: 6,
+                ..ExactVersion::default()
+            },
             PathBuf::from(python36_path),
         );
         let python37_path = "/path/to/3/7/python";
         executables.insert(
-            ExactVersion { major: 3, minor: 7 },
+            ExactVersion {
+                major: 3,
+                minor: 7,
+                ..ExactVersion::default()
+            },
             PathBuf::from(python37_path),
         );
 
         // Tests try not to make any guarantees about explicit formatting, just
         // that the interpreters are in descending order of version and the
         // interpreter version comes before the path (i.e. in column order).
-        let executables_list = list_executables(&executables).unwrap();
+        let executables_list = list_executables(&executables, ListFormat::Table).unwrap();
         // No critical data is missing.
         assert!(executables_list.contains("2.7"));
         assert!(executables_list.contains(python27_path));
@@ -414,6 +769,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_list_executables_json() {
+        let mut executables: HashMap<ExactVersion, PathBuf> = HashMap::new();
+        executables.insert(
+            ExactVersion {
+                major: 3,
+                minor: 6,
+                ..ExactVersion::default()
+            },
+            PathBuf::from("/path/to/3/6/python"),
+        );
+        executables.insert(
+            ExactVersion {
+                major: 3,
+                minor: 7,
+                ..ExactVersion::default()
+            },
+            PathBuf::from("/path/to/3/7/python"),
+        );
+
+        let json = list_executables(&executables, ListFormat::Json).unwrap();
+
+        // Same ordering guarantee as the table: newest first.
+        assert!(json.find("3.7").unwrap() < json.find("3.6").unwrap());
+        assert!(json.contains(r#""version":"3.7""#));
+        assert!(json.contains(r#""path":"/path/to/3/7/python""#));
+    }
+
+    #[test_case("" => "" ; "empty string")]
+    #[test_case(r"C:\Python\python.exe" => r"C:\\Python\\python.exe" ; "backslash")]
+    #[test_case(r#"/path/with"quote"#  => r#"/path/with\"quote"# ; "quote")]
+    #[test_case("/path/with\nnewline" => r"/path/with\nnewline" ; "newline")]
+    #[test_case("/path/with\ttab" => r"/path/with\ttab" ; "tab")]
+    #[test_case("/path/with\u{1}control" => r"/path/with\u0001control" ; "other control character")]
+    fn json_escape_str_tests(value: &str) -> String {
+        json_escape_str(value)
+    }
+
+    #[test_case(false, None => ListFormat::Table ; "no flag, no env var")]
+    #[test_case(true, None => ListFormat::Json ; "json flag wins")]
+    #[test_case(false, Some("json") => ListFormat::Json ; "env var requests json")]
+    #[test_case(false, Some("JSON") => ListFormat::Json ; "env var is case-insensitive")]
+    #[test_case(false, Some("table") => ListFormat::Table ; "env var requesting something else is ignored")]
+    fn list_format_from_json_flag_tests(json_flag: bool, env_value: Option<&str>) -> ListFormat {
+        let _guard = PY_LIST_FORMAT_LOCK.lock().unwrap();
+        match env_value {
+            Some(value) => env::set_var("PY_LIST_FORMAT", value),
+            None => env::remove_var("PY_LIST_FORMAT"),
+        }
+        let format = ListFormat::from_json_flag(json_flag);
+        env::remove_var("PY_LIST_FORMAT");
+        format
+    }
+
     #[test]
     fn test_venv_executable_path() {
         let venv_root = "/path/to/venv";
@@ -423,6 +832,78 @@ mod tests {
         );
     }
 
+    /// Creates a fresh, empty directory under [`env::temp_dir`] for a
+    /// `python_version_from_ancestors` test to populate.
+    fn temp_dir(name: &str) -> PathBuf {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let unique = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let dir = env::temp_dir().join(format!(
+            "python-launcher-test-{}-{}-{}",
+            std::process::id(),
+            name,
+            unique
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn python_version_from_ancestors_finds_file_at_the_starting_directory() {
+        let dir = temp_dir("at_cwd");
+        std::fs::write(dir.join(".python-version"), "3.11").unwrap();
+
+        assert_eq!(
+            python_version_from_ancestors(&dir),
+            Some(RequestedVersion::Exact(3, 11))
+        );
+    }
+
+    #[test]
+    fn python_version_from_ancestors_finds_file_in_a_parent_directory() {
+        let parent = temp_dir("at_parent");
+        let child = parent.join("child");
+        std::fs::create_dir(&child).unwrap();
+        std::fs::write(parent.join(".python-version"), "3.9").unwrap();
+
+        assert_eq!(
+            python_version_from_ancestors(&child),
+            Some(RequestedVersion::Exact(3, 9))
+        );
+    }
+
+    #[test]
+    fn python_version_from_ancestors_skips_blank_and_comment_lines() {
+        let dir = temp_dir("skips_blank_and_comment");
+        std::fs::write(dir.join(".python-version"), "\n# a comment\n3.12\n").unwrap();
+
+        assert_eq!(
+            python_version_from_ancestors(&dir),
+            Some(RequestedVersion::Exact(3, 12))
+        );
+    }
+
+    #[test]
+    fn python_version_from_ancestors_falls_through_to_parent_on_parse_failure() {
+        let parent = temp_dir("falls_through");
+        let child = parent.join("child");
+        std::fs::create_dir(&child).unwrap();
+        std::fs::write(child.join(".python-version"), "3.6.4").unwrap();
+        std::fs::write(parent.join(".python-version"), "3.9").unwrap();
+
+        assert_eq!(
+            python_version_from_ancestors(&child),
+            Some(RequestedVersion::Exact(3, 9))
+        );
+    }
+
+    #[test]
+    fn python_version_from_ancestors_finds_nothing() {
+        let dir = temp_dir("finds_nothing");
+
+        assert_eq!(python_version_from_ancestors(&dir), None);
+    }
+
     #[test_case("/usr/bin/python" => None ; "missing shebang comment")]
     #[test_case("# /usr/bin/python" => None ; "missing exclamation point")]
     #[test_case("! /usr/bin/python" => None ; "missing octothorpe")]
@@ -435,6 +916,11 @@ mod tests {
     #[test_case("#! /usr/bin/python3.7" => Some(RequestedVersion::Exact(3, 7)) ; "typical 'python' with minor version")]
     #[test_case("#! python3.7" => Some(RequestedVersion::Exact(3, 7)) ; "bare 'python' with minor version")]
     #[test_case("#!/usr/bin/python" => Some(RequestedVersion::Any) ; "no space between shebang and path")]
+    #[test_case("#! /usr/bin/env -S python3.11 -X foo" => Some(RequestedVersion::Exact(3, 11)) ; "env -S with interpreter arguments")]
+    #[test_case("#! /home/me/proj/.venv/bin/python3.12" => Some(RequestedVersion::Exact(3, 12)) ; "arbitrary venv interpreter path")]
+    #[test_case("#! pypy3" => Some(RequestedVersion::MajorOnly(3)) ; "bare 'pypy' with major version")]
+    #[test_case("#! /usr/bin/env pypy" => Some(RequestedVersion::Any) ; "env 'pypy'")]
+    #[test_case("#! /usr/bin/python-config" => None ; "non-interpreter sharing the 'python' prefix")]
     fn parse_python_shebang_tests(shebang: &str) -> Option<RequestedVersion> {
         parse_python_shebang(&mut shebang.as_bytes())
     }
@@ -0,0 +1,458 @@
+//! Core interpreter-discovery logic for the Python Launcher.
+//!
+//! [`crate::cli`] turns `argv` into an [`cli::Action`]; this module
+//! provides the pieces it relies on: [`RequestedVersion`]/[`ExactVersion`]
+//! describe what was asked for versus what's actually installed, and
+//! [`find_executable`]/[`all_executables`] do the work of locating
+//! interpreters on `PATH`.
+
+pub mod cli;
+
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// The Python implementation an [`ExactVersion`] was built by.
+///
+/// Ordered so that [`Implementation::CPython`] sorts highest within an
+/// [`ExactVersion`] of the same major.minor. [`all_executables`] keeps only
+/// the first interpreter found for each major.minor, so [`find_executable`]
+/// never chooses between implementations of the same major.minor.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Implementation {
+    PyPy,
+    #[default]
+    CPython,
+}
+
+/// How close to a stable release an [`ExactVersion`] is.
+///
+/// Ordered so that [`ReleaseLevel::Final`] sorts highest within an
+/// [`ExactVersion`] of the same major.minor. [`all_executables`] keeps only
+/// the first interpreter found for each major.minor, so [`find_executable`]
+/// never chooses between release levels of the same major.minor.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum ReleaseLevel {
+    Alpha,
+    Beta,
+    Candidate,
+    #[default]
+    Final,
+}
+
+/// A specific, installed Python interpreter's version.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct ExactVersion {
+    pub major: u8,
+    pub minor: u8,
+    pub implementation: Implementation,
+    pub release_level: ReleaseLevel,
+}
+
+impl ExactVersion {
+    /// Whether this is the kind of interpreter [`RequestedVersion::Default`]
+    /// is willing to pick implicitly: a final release of CPython.
+    fn is_stable_cpython(&self) -> bool {
+        self.implementation == Implementation::CPython && self.release_level == ReleaseLevel::Final
+    }
+}
+
+impl fmt::Display for ExactVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// A version of Python requested by the user, e.g. via a CLI flag,
+/// environment variable, or shebang.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RequestedVersion {
+    /// No version was specified and every installed interpreter is
+    /// eligible to be chosen, including pre-release builds and alternate
+    /// implementations (e.g. PyPy). Used for `--any`/`-a` and for `-h`/
+    /// `--help`.
+    Any,
+    /// No version was specified and the search should only consider a
+    /// stable CPython build. Used for an implicit search, i.e. when no
+    /// launcher arguments are given at all.
+    Default,
+    /// Only the major version was specified (e.g. `-3`).
+    MajorOnly(u8),
+    /// Both the major and minor version were specified (e.g. `-3.6`).
+    Exact(u8, u8),
+    /// A bounded interval of major.minor versions (e.g. `-=3.11` or
+    /// `->=3.11,<3.13`).
+    Range {
+        lower: Option<(u8, u8)>,
+        upper: Option<(u8, u8)>,
+        lower_inclusive: bool,
+        upper_inclusive: bool,
+    },
+}
+
+impl RequestedVersion {
+    /// Whether `version` satisfies this request.
+    fn matches(&self, version: &ExactVersion) -> bool {
+        match self {
+            RequestedVersion::Any => true,
+            // Pre-release builds and alternate implementations are only
+            // selected when asked for explicitly (`RequestedVersion::Any`),
+            // never implicitly.
+            RequestedVersion::Default => version.is_stable_cpython(),
+            RequestedVersion::MajorOnly(major) => version.major == *major,
+            RequestedVersion::Exact(major, minor) => {
+                version.major == *major && version.minor == *minor
+            }
+            RequestedVersion::Range {
+                lower,
+                upper,
+                lower_inclusive,
+                upper_inclusive,
+            } => {
+                let candidate = (version.major, version.minor);
+                let above_lower = lower.is_none_or(|bound| {
+                    if *lower_inclusive {
+                        candidate >= bound
+                    } else {
+                        candidate > bound
+                    }
+                });
+                let below_upper = upper.is_none_or(|bound| {
+                    if *upper_inclusive {
+                        candidate <= bound
+                    } else {
+                        candidate < bound
+                    }
+                });
+                above_lower && below_upper
+            }
+        }
+    }
+
+    /// The `PY_PYTHON*` environment variable consulted for this request,
+    /// if any.
+    ///
+    /// For a [`RequestedVersion::Range`] this is based on the lower bound's
+    /// major version (e.g. `->=3.11,<3.13` consults `PY_PYTHON3`); a range
+    /// with no lower bound doesn't have an environment variable to check.
+    pub fn env_var(&self) -> Option<String> {
+        match self {
+            RequestedVersion::Any | RequestedVersion::Default => Some("PY_PYTHON".to_string()),
+            RequestedVersion::MajorOnly(major) => Some(format!("PY_PYTHON{}", major)),
+            RequestedVersion::Exact(major, minor) => Some(format!("PY_PYTHON{}{}", major, minor)),
+            RequestedVersion::Range { lower, .. } => {
+                lower.map(|(major, _)| format!("PY_PYTHON{}", major))
+            }
+        }
+    }
+}
+
+impl FromStr for RequestedVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.is_empty() {
+            return Ok(RequestedVersion::Any);
+        }
+
+        let mut parts = s.splitn(3, '.');
+        let major: u8 = parts
+            .next()
+            .filter(|part| !part.is_empty())
+            .ok_or_else(|| Error::InvalidVersion(s.to_string()))?
+            .parse()
+            .map_err(|_| Error::InvalidVersion(s.to_string()))?;
+
+        match (parts.next(), parts.next()) {
+            (None, _) => Ok(RequestedVersion::MajorOnly(major)),
+            (Some(minor), None) => minor
+                .parse()
+                .map(|minor| RequestedVersion::Exact(major, minor))
+                .map_err(|_| Error::InvalidVersion(s.to_string())),
+            // A micro version (e.g. "3.6.4") isn't supported.
+            (Some(_), Some(_)) => Err(Error::InvalidVersion(s.to_string())),
+        }
+    }
+}
+
+/// Errors that can occur while parsing arguments or locating an
+/// interpreter.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Error {
+    /// An illegal combination of CLI arguments was given; holds the path to
+    /// the launcher and the offending flag.
+    IllegalArgument(PathBuf, String),
+    /// No executable could be found satisfying the request.
+    NoExecutableFound(RequestedVersion),
+    /// A version specifier could not be parsed.
+    InvalidVersion(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IllegalArgument(launcher_path, flag) => write!(
+                f,
+                "'{}' cannot be combined with other arguments ({})",
+                flag,
+                launcher_path.display()
+            ),
+            Error::NoExecutableFound(version) => {
+                write!(f, "no Python executable found matching {:?}", version)
+            }
+            Error::InvalidVersion(spec) => {
+                write!(f, "'{}' is not a valid version specifier", spec)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Matches the final path component of an interpreter found on `PATH`
+/// against `python3.Y`.
+static PYTHON_MINOR_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^python3\.(\d{1,2})$").unwrap());
+
+/// Matches the final path component of an interpreter found on `PATH`
+/// against `pypy3` or `pypy3.Y`.
+static PYPY_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^pypy3(?:\.\d{1,2})?$").unwrap());
+
+/// Parses the release-level suffix (e.g. `a1`, `b2`, `rc1`) trailing a
+/// micro version, as used in CPython's own `--version` output.
+fn release_level_from_suffix(suffix: &str) -> ReleaseLevel {
+    if suffix.to_ascii_lowercase().contains("rc") {
+        ReleaseLevel::Candidate
+    } else if suffix.contains('b') {
+        ReleaseLevel::Beta
+    } else if suffix.contains('a') {
+        ReleaseLevel::Alpha
+    } else {
+        ReleaseLevel::Final
+    }
+}
+
+/// Runs `path --version` and parses its `Python X.Y.Z[release-suffix]`
+/// output into an [`ExactVersion`].
+fn exact_version_from_version_output(path: &Path) -> Option<ExactVersion> {
+    let output = Command::new(path).arg("--version").output().ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // Python 2 prints `--version`'s output to stderr; Python 3 to stdout.
+    // PyPy additionally prints a `[PyPy ...]` line identifying itself.
+    let combined = format!("{}{}", stdout, stderr);
+    let version_line = combined
+        .lines()
+        .find(|line| line.trim_start().starts_with("Python "))?;
+
+    // Only the `X.Y.Z[release-suffix]` token; PyPy follows it with a
+    // `(build-hash, date)` that must not be read as a release suffix.
+    let version_text = version_line
+        .trim()
+        .strip_prefix("Python ")?
+        .split_whitespace()
+        .next()?;
+    let mut parts = version_text.splitn(3, '.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let release_level = parts.next().map_or(ReleaseLevel::Final, release_level_from_suffix);
+
+    let implementation = if combined.contains("PyPy") {
+        Implementation::PyPy
+    } else {
+        Implementation::CPython
+    };
+
+    Some(ExactVersion {
+        major,
+        minor,
+        implementation,
+        release_level,
+    })
+}
+
+/// Determines the version a `PATH` entry provides, if it looks like a
+/// Python interpreter at all.
+///
+/// The file name only tells us it's worth checking -- `python`, `python3`,
+/// a `python3.Y`/`pypy3`/`pypy3.Y` name don't reveal whether the build is a
+/// stable release or pre-release, or (for the generic names) which
+/// implementation it is. `--version` is run to confirm.
+fn exact_version_from_candidate(path: &Path) -> Option<ExactVersion> {
+    let file_name = path.file_name()?.to_str()?;
+
+    let looks_like_interpreter = file_name == "python"
+        || file_name == "python3"
+        || PYTHON_MINOR_RE.is_match(file_name)
+        || PYPY_RE.is_match(file_name);
+
+    if !looks_like_interpreter {
+        return None;
+    }
+
+    exact_version_from_version_output(path)
+}
+
+/// Scans every directory on `PATH` for Python interpreters, returning the
+/// path found for each [`ExactVersion`].
+///
+/// Directories are walked in `PATH` order and, within a directory, file
+/// system order; the first match for a given `major.minor` wins, mirroring
+/// how a shell resolves a bare command name. This both fills in
+/// `pythonX.Y`-named binaries that `python`/`python3` don't cover (e.g. a
+/// `python3.12` found further down `PATH` than the `python3` symlink) and
+/// resolves the generic names themselves.
+pub fn all_executables() -> HashMap<ExactVersion, PathBuf> {
+    let mut executables = HashMap::new();
+    // Tracks which major.minor pairs are already spoken for, independent of
+    // how they were resolved -- a `pythonX.Y` name and a later `python3`
+    // that happens to report the same minor version should not both get an
+    // entry.
+    let mut resolved_versions: HashSet<(u8, u8)> = HashSet::new();
+
+    let path_var = match env::var_os("PATH") {
+        Some(path_var) => path_var,
+        None => {
+            log::warn!("PATH is not set");
+            return executables;
+        }
+    };
+
+    for dir in env::split_paths(&path_var) {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(version) = exact_version_from_candidate(&path) else {
+                continue;
+            };
+
+            if !resolved_versions.insert((version.major, version.minor)) {
+                log::debug!("Already resolved {}, skipping {}", version, path.display());
+                continue;
+            }
+
+            log::debug!("Found {} at {}", version, path.display());
+            executables.insert(version, path);
+        }
+    }
+
+    executables
+}
+
+/// Finds the path to an installed interpreter satisfying
+/// `requested_version`, preferring the newest match.
+pub fn find_executable(requested_version: RequestedVersion) -> Option<PathBuf> {
+    all_executables()
+        .into_iter()
+        .filter(|(version, _)| requested_version.matches(version))
+        .max_by_key(|(version, _)| *version)
+        .map(|(_, path)| path)
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    fn v(major: u8, minor: u8) -> ExactVersion {
+        ExactVersion {
+            major,
+            minor,
+            ..ExactVersion::default()
+        }
+    }
+
+    fn v_with(
+        major: u8,
+        minor: u8,
+        implementation: Implementation,
+        release_level: ReleaseLevel,
+    ) -> ExactVersion {
+        ExactVersion {
+            major,
+            minor,
+            implementation,
+            release_level,
+        }
+    }
+
+    #[test_case("python" => true ; "bare python")]
+    #[test_case("python3" => true ; "bare python3")]
+    #[test_case("python3.12" => true ; "pythonX.Y")]
+    #[test_case("pypy3" => true ; "bare pypy3")]
+    #[test_case("pypy3.10" => true ; "pypy3.Y")]
+    #[test_case("python2.7" => false ; "python 2.x is not considered")]
+    #[test_case("pythonX.Y" => false ; "non-numeric minor is not matched")]
+    #[test_case("not-python" => false ; "unrelated file name is not matched")]
+    fn exact_version_from_candidate_name_filter_tests(file_name: &str) -> bool {
+        // Only exercises the file-name filter; confirming which names are
+        // worth an actual `--version` call.
+        file_name == "python"
+            || file_name == "python3"
+            || PYTHON_MINOR_RE.is_match(file_name)
+            || PYPY_RE.is_match(file_name)
+    }
+
+    #[test_case(v(3, 13) => true ; "stable CPython")]
+    #[test_case(v_with(3, 13, Implementation::CPython, ReleaseLevel::Candidate) => false ; "CPython release candidate is not stable")]
+    #[test_case(v_with(3, 13, Implementation::PyPy, ReleaseLevel::Final) => false ; "stable PyPy is not stable CPython")]
+    fn is_stable_cpython_tests(version: ExactVersion) -> bool {
+        version.is_stable_cpython()
+    }
+
+    #[test_case(RequestedVersion::Default, v(3, 13) => true ; "Default matches stable CPython")]
+    #[test_case(RequestedVersion::Default, v_with(3, 13, Implementation::CPython, ReleaseLevel::Candidate) => false ; "Default rejects a pre-release")]
+    #[test_case(RequestedVersion::Default, v_with(3, 13, Implementation::PyPy, ReleaseLevel::Final) => false ; "Default rejects a non-CPython implementation")]
+    #[test_case(RequestedVersion::Any, v_with(3, 13, Implementation::PyPy, ReleaseLevel::Final) => true ; "Any accepts a non-CPython implementation")]
+    #[test_case(RequestedVersion::Any, v_with(3, 14, Implementation::CPython, ReleaseLevel::Candidate) => true ; "Any accepts a pre-release")]
+    fn requested_version_default_vs_any_tests(requested: RequestedVersion, version: ExactVersion) -> bool {
+        requested.matches(&version)
+    }
+
+    #[test_case("" => Ok(RequestedVersion::Any))]
+    #[test_case("3" => Ok(RequestedVersion::MajorOnly(3)))]
+    #[test_case("3.9" => Ok(RequestedVersion::Exact(3, 9)))]
+    #[test_case("3.6.4" => Err(Error::InvalidVersion("3.6.4".to_string())) ; "micro version is rejected")]
+    #[test_case("x" => Err(Error::InvalidVersion("x".to_string())) ; "non-numeric major is rejected")]
+    fn requested_version_from_str_tests(s: &str) -> Result<RequestedVersion> {
+        RequestedVersion::from_str(s)
+    }
+
+    #[test_case(RequestedVersion::Any => Some("PY_PYTHON".to_string()))]
+    #[test_case(RequestedVersion::Default => Some("PY_PYTHON".to_string()))]
+    #[test_case(RequestedVersion::MajorOnly(3) => Some("PY_PYTHON3".to_string()))]
+    #[test_case(RequestedVersion::Exact(3, 11) => Some("PY_PYTHON311".to_string()))]
+    #[test_case(RequestedVersion::Range { lower: Some((3, 11)), upper: Some((3, 13)), lower_inclusive: true, upper_inclusive: false } => Some("PY_PYTHON3".to_string()) ; "range env var follows the lower bound's major")]
+    #[test_case(RequestedVersion::Range { lower: None, upper: Some((3, 13)), lower_inclusive: false, upper_inclusive: false } => None ; "range with no lower bound has no env var")]
+    fn env_var_tests(requested: RequestedVersion) -> Option<String> {
+        requested.env_var()
+    }
+
+    #[test_case(RequestedVersion::Any, v(2, 7) => true ; "any matches anything")]
+    #[test_case(RequestedVersion::MajorOnly(3), v(3, 6) => true ; "majoronly matches same major")]
+    #[test_case(RequestedVersion::MajorOnly(3), v(2, 7) => false ; "majoronly rejects other major")]
+    #[test_case(RequestedVersion::Exact(3, 6), v(3, 6) => true ; "exact matches same version")]
+    #[test_case(RequestedVersion::Exact(3, 6), v(3, 7) => false ; "exact rejects other minor")]
+    #[test_case(RequestedVersion::Range { lower: Some((3, 11)), upper: Some((3, 13)), lower_inclusive: true, upper_inclusive: false }, v(3, 12) => true ; "within a bounded range")]
+    #[test_case(RequestedVersion::Range { lower: Some((3, 11)), upper: Some((3, 13)), lower_inclusive: true, upper_inclusive: false }, v(3, 13) => false ; "at the exclusive upper bound of a bounded range")]
+    #[test_case(RequestedVersion::Range { lower: Some((3, 11)), upper: None, lower_inclusive: true, upper_inclusive: false }, v(3, 11) => true ; "inclusive lower bound matches the bound itself")]
+    #[test_case(RequestedVersion::Range { lower: Some((3, 11)), upper: None, lower_inclusive: false, upper_inclusive: false }, v(3, 11) => false ; "exclusive lower bound rejects the bound itself")]
+    #[test_case(RequestedVersion::Range { lower: None, upper: Some((3, 13)), lower_inclusive: false, upper_inclusive: true }, v(3, 13) => true ; "inclusive upper bound matches the bound itself")]
+    #[test_case(RequestedVersion::Range { lower: None, upper: Some((3, 13)), lower_inclusive: false, upper_inclusive: false }, v(3, 13) => false ; "exclusive upper bound rejects the bound itself")]
+    #[test_case(RequestedVersion::Range { lower: Some((3, 12)), upper: None, lower_inclusive: true, upper_inclusive: false }, v(4, 0) => true ; "lower bound compares major.minor as a pair, not independently")]
+    fn requested_version_matches_tests(requested: RequestedVersion, version: ExactVersion) -> bool {
+        requested.matches(&version)
+    }
+}
@@ -0,0 +1,40 @@
+use std::env;
+use std::process::{self, Command};
+
+use python_launcher::cli::Action;
+
+fn main() {
+    env_logger::init();
+
+    let argv: Vec<String> = env::args().collect();
+
+    let exit_code = match Action::from_main(&argv) {
+        Ok(Action::Help(message, python_path)) => {
+            print!("{}", message);
+            run(python_path, vec!["-h".to_string()])
+        }
+        Ok(Action::List(listing)) => {
+            print!("{}", listing);
+            0
+        }
+        Ok(Action::Execute {
+            executable, args, ..
+        }) => run(executable, args),
+        Err(error) => {
+            eprintln!("error: {}", error);
+            1
+        }
+    };
+
+    process::exit(exit_code);
+}
+
+fn run(executable: std::path::PathBuf, args: Vec<String>) -> i32 {
+    match Command::new(executable).args(args).status() {
+        Ok(status) => status.code().unwrap_or(1),
+        Err(error) => {
+            eprintln!("error: {}", error);
+            1
+        }
+    }
+}